@@ -0,0 +1,14 @@
+pub mod model;
+
+use derive_new::new;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgPool, Postgres};
+
+#[derive(new, Clone)]
+pub struct ConnectionPool(PgPool);
+
+impl ConnectionPool {
+    pub async fn acquire(&self) -> sqlx::Result<PoolConnection<Postgres>> {
+        self.0.acquire().await
+    }
+}
@@ -0,0 +1,53 @@
+use kernel::model::{
+    book::Book,
+    category::Category,
+    id::{BookId, CategoryId},
+};
+use serde::Deserialize;
+use sqlx::types::Json;
+
+pub struct BookRow {
+    pub book_id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub description: String,
+    pub categories: Json<Vec<CategoryRow>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryRow {
+    pub category_id: CategoryId,
+    pub name: String,
+}
+
+impl From<CategoryRow> for Category {
+    fn from(row: CategoryRow) -> Self {
+        Self {
+            id: row.category_id,
+            name: row.name,
+        }
+    }
+}
+
+impl From<BookRow> for Book {
+    fn from(row: BookRow) -> Self {
+        let BookRow {
+            book_id,
+            title,
+            author,
+            isbn,
+            description,
+            categories,
+        } = row;
+
+        Self {
+            id: book_id,
+            title,
+            author,
+            isbn,
+            description,
+            categories: categories.0.into_iter().map(Category::from).collect(),
+        }
+    }
+}
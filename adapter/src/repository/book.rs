@@ -1,81 +1,575 @@
 use async_trait::async_trait;
 use derive_new::new;
 use kernel::model::{
-    book::{event::CreateBook, Book},
-    id::BookId,
+    book::{
+        event::{CreateBook, UpdateBook},
+        Book, PaginatedBooks,
+    },
+    id::{AuthorId, BookId, CategoryId},
+    list::SortOrder,
 };
 use kernel::repository::book::BookRepository;
 use shared::error::{AppError, AppResult};
+use sqlx::{
+    types::{Json, Uuid},
+    PgConnection,
+};
 
+use crate::database::model::book::{BookRow, CategoryRow};
 use crate::database::ConnectionPool;
-use crate::database::model::book::BookRow;
 
 #[derive(new)]
 pub struct BookRepositoryImpl {
     db: ConnectionPool,
 }
 
-#[async_trait]
-impl BookRepository for BookRepositoryImpl {
-    async fn create(&self, event: CreateBook) -> AppResult<()> {
-        sqlx::query!(
+// author / category は books と多対多なので、1冊分の完全な情報を
+// 取得するには JOIN と集約が必要になる。この SELECT の形は find_all /
+// find_all_paginated / find_by_id / find_by_author / find_by_category で
+// 共通しているが、query_as! はリテラルの文字列しか受け付けないため、
+// やむを得ず都度書き下す。
+impl BookRepositoryImpl {
+    async fn create_with(conn: &mut PgConnection, event: CreateBook) -> AppResult<BookId> {
+        // books への INSERT と book_authors への INSERT は片方だけ成功すると
+        // 著者のいない蔵書が残ってしまうため、1つのトランザクション
+        // （`conn` がすでにトランザクション中ならセーブポイント）として扱う。
+        let mut tx = conn.begin().await.map_err(AppError::TransactionError)?;
+
+        let row = sqlx::query!(
             r#"
-                INSERT INTO books (title, author, isbn, description)
-                VALUES($1, $2, $3, $4)
+                INSERT INTO books (title, isbn, description)
+                VALUES ($1, $2, $3)
+                RETURNING book_id
             "#,
             event.title,
-            event.author,
             event.isbn,
             event.description
         )
-        .execute(self.db.inner_ref())
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        .fetch_one(&mut *tx)
+        .await?;
 
-        Ok(())
+        let book_id = BookId::new(row.book_id);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO book_authors (book_id, author_id) VALUES ($1, $2)
+            "#,
+            book_id as _,
+            event.author_id as _
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(book_id)
     }
 
-    async fn find_all(&self) -> AppResult<Vec<Book>> {
+    async fn find_all_with(conn: &mut PgConnection) -> AppResult<Vec<Book>> {
         let rows: Vec<BookRow> = sqlx::query_as!(
             BookRow,
             r#"
                 SELECT
-                    book_id,
-                    title,
-                    author,
-                    isbn,
-                    description
+                    books.book_id,
+                    books.title,
+                    authors.name as author,
+                    books.isbn,
+                    books.description,
+                    COALESCE(
+                        json_agg(
+                            json_build_object('category_id', categories.category_id, 'name', categories.name)
+                        ) FILTER (WHERE categories.category_id IS NOT NULL),
+                        '[]'
+                    ) as "categories!: Json<Vec<CategoryRow>>"
                 FROM books
-                ORDER BY created_at DESC
+                INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                INNER JOIN authors ON authors.author_id = book_authors.author_id
+                LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                GROUP BY books.book_id, authors.name
+                ORDER BY books.created_at DESC
             "#
         )
-        .fetch_all(self.db.inner_ref())
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        .fetch_all(&mut *conn)
+        .await?;
 
         Ok(rows.into_iter().map(Book::from).collect())
     }
 
-    async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>> {
-        let rows: Option<BookRow> = sqlx::query_as!(
+    async fn find_by_id_with(conn: &mut PgConnection, book_id: BookId) -> AppResult<Option<Book>> {
+        let row: Option<BookRow> = sqlx::query_as!(
             BookRow,
             r#"
                 SELECT
-                    book_id,
-                    title,
-                    author,
-                    isbn,
-                    description
+                    books.book_id,
+                    books.title,
+                    authors.name as author,
+                    books.isbn,
+                    books.description,
+                    COALESCE(
+                        json_agg(
+                            json_build_object('category_id', categories.category_id, 'name', categories.name)
+                        ) FILTER (WHERE categories.category_id IS NOT NULL),
+                        '[]'
+                    ) as "categories!: Json<Vec<CategoryRow>>"
                 FROM books
-                WHERE book_id = $1
+                INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                INNER JOIN authors ON authors.author_id = book_authors.author_id
+                LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                WHERE books.book_id = $1
+                GROUP BY books.book_id, authors.name
             "#,
             book_id as _ // query_as!マクロのコンパイルによる型チェックを無効化
         )
-        .fetch_optional(self.db.inner_ref())
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(row.map(Book::from))
+    }
+
+    async fn update_with(conn: &mut PgConnection, event: UpdateBook) -> AppResult<()> {
+        // UPDATE books と book_authors の張り替えのどちらかだけ成功すると
+        // 蔵書が著者なしになり INNER JOIN book_authors を使う検索から
+        // 消えてしまう。create_with と同様に1つのトランザクション
+        // （`conn` がすでにトランザクション中ならセーブポイント）として扱う。
+        let mut tx = conn.begin().await.map_err(AppError::TransactionError)?;
+
+        let res = sqlx::query!(
+            r#"
+                UPDATE books
+                SET title = $2, isbn = $3, description = $4
+                WHERE book_id = $1
+            "#,
+            event.book_id as _,
+            event.title,
+            event.isbn,
+            event.description
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(format!(
+                "Book not found. (book_id: {})",
+                event.book_id.raw()
+            )));
+        }
+
+        // 著者は1冊につき1件の book_authors 行として保持しているので、
+        // 張り替えは「削除してから挿入し直す」のがもっとも単純。
+        sqlx::query!(
+            r#"DELETE FROM book_authors WHERE book_id = $1"#,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"INSERT INTO book_authors (book_id, author_id) VALUES ($1, $2)"#,
+            event.book_id as _,
+            event.author_id as _
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn delete_with(conn: &mut PgConnection, book_id: BookId) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM books WHERE book_id = $1
+            "#,
+            book_id as _
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(format!(
+                "Book not found. (book_id: {})",
+                book_id.raw()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn find_all_paginated_with(
+        conn: &mut PgConnection,
+        limit: i64,
+        cursor: Option<BookId>,
+        order: SortOrder,
+    ) -> AppResult<PaginatedBooks> {
+        let fetch_limit = limit + 1;
+
+        let cursor_ts = match cursor {
+            Some(cursor_id) => {
+                let rec = sqlx::query!(
+                    r#"SELECT created_at FROM books WHERE book_id = $1"#,
+                    cursor_id as _
+                )
+                .fetch_optional(&mut *conn)
+                .await?
+                .ok_or_else(|| {
+                    AppError::EntityNotFound(format!(
+                        "Book not found. (book_id: {})",
+                        cursor_id.raw()
+                    ))
+                })?;
+
+                Some(rec.created_at)
+            }
+            None => None,
+        };
+        // cursor が Some なら上の問い合わせが必ず cursor_ts も Some にするか
+        // EntityNotFound で早期リターンする。zip しておくことで (Some, None)
+        // という本来起こり得ない組み合わせを型レベルで消し、match を exhaustive にする。
+        let cursor_info = cursor.zip(cursor_ts);
+
+        // query_as! はクエリ文字列を静的に検証するため、$1 に ORDER BY の
+        // 方向を差し込むことはできない。カーソルの有無とソート順の
+        // 組み合わせごとにクエリを分岐させる。
+        let rows: Vec<BookRow> = match (order, cursor_info) {
+            (SortOrder::Desc, Some((cursor_id, cursor_ts))) => sqlx::query_as!(
+                BookRow,
+                r#"
+                    SELECT
+                        books.book_id,
+                        books.title,
+                        authors.name as author,
+                        books.isbn,
+                        books.description,
+                        COALESCE(
+                            json_agg(
+                                json_build_object('category_id', categories.category_id, 'name', categories.name)
+                            ) FILTER (WHERE categories.category_id IS NOT NULL),
+                            '[]'
+                        ) as "categories!: Json<Vec<CategoryRow>>"
+                    FROM books
+                    INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                    INNER JOIN authors ON authors.author_id = book_authors.author_id
+                    LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                    LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                    WHERE (books.created_at, books.book_id) < ($1, $2)
+                    GROUP BY books.book_id, authors.name
+                    ORDER BY books.created_at DESC, books.book_id DESC
+                    LIMIT $3
+                "#,
+                cursor_ts,
+                cursor_id as _,
+                fetch_limit
+            )
+            .fetch_all(&mut *conn)
+            .await?,
+            (SortOrder::Asc, Some((cursor_id, cursor_ts))) => sqlx::query_as!(
+                BookRow,
+                r#"
+                    SELECT
+                        books.book_id,
+                        books.title,
+                        authors.name as author,
+                        books.isbn,
+                        books.description,
+                        COALESCE(
+                            json_agg(
+                                json_build_object('category_id', categories.category_id, 'name', categories.name)
+                            ) FILTER (WHERE categories.category_id IS NOT NULL),
+                            '[]'
+                        ) as "categories!: Json<Vec<CategoryRow>>"
+                    FROM books
+                    INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                    INNER JOIN authors ON authors.author_id = book_authors.author_id
+                    LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                    LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                    WHERE (books.created_at, books.book_id) > ($1, $2)
+                    GROUP BY books.book_id, authors.name
+                    ORDER BY books.created_at ASC, books.book_id ASC
+                    LIMIT $3
+                "#,
+                cursor_ts,
+                cursor_id as _,
+                fetch_limit
+            )
+            .fetch_all(&mut *conn)
+            .await?,
+            (SortOrder::Desc, None) => sqlx::query_as!(
+                BookRow,
+                r#"
+                    SELECT
+                        books.book_id,
+                        books.title,
+                        authors.name as author,
+                        books.isbn,
+                        books.description,
+                        COALESCE(
+                            json_agg(
+                                json_build_object('category_id', categories.category_id, 'name', categories.name)
+                            ) FILTER (WHERE categories.category_id IS NOT NULL),
+                            '[]'
+                        ) as "categories!: Json<Vec<CategoryRow>>"
+                    FROM books
+                    INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                    INNER JOIN authors ON authors.author_id = book_authors.author_id
+                    LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                    LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                    GROUP BY books.book_id, authors.name
+                    ORDER BY books.created_at DESC, books.book_id DESC
+                    LIMIT $1
+                "#,
+                fetch_limit
+            )
+            .fetch_all(&mut *conn)
+            .await?,
+            (SortOrder::Asc, None) => sqlx::query_as!(
+                BookRow,
+                r#"
+                    SELECT
+                        books.book_id,
+                        books.title,
+                        authors.name as author,
+                        books.isbn,
+                        books.description,
+                        COALESCE(
+                            json_agg(
+                                json_build_object('category_id', categories.category_id, 'name', categories.name)
+                            ) FILTER (WHERE categories.category_id IS NOT NULL),
+                            '[]'
+                        ) as "categories!: Json<Vec<CategoryRow>>"
+                    FROM books
+                    INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                    INNER JOIN authors ON authors.author_id = book_authors.author_id
+                    LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                    LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                    GROUP BY books.book_id, authors.name
+                    ORDER BY books.created_at ASC, books.book_id ASC
+                    LIMIT $1
+                "#,
+                fetch_limit
+            )
+            .fetch_all(&mut *conn)
+            .await?,
+        };
+
+        let has_next = rows.len() as i64 > limit;
+        let mut items: Vec<Book> = rows.into_iter().map(Book::from).collect();
+        if has_next {
+            items.truncate(limit as usize);
+        }
+        let next_cursor = has_next.then(|| items.last().map(|book| book.id)).flatten();
+
+        Ok(PaginatedBooks { items, next_cursor })
+    }
+
+    async fn find_by_author_with(
+        conn: &mut PgConnection,
+        author_id: AuthorId,
+    ) -> AppResult<Vec<Book>> {
+        let rows: Vec<BookRow> = sqlx::query_as!(
+            BookRow,
+            r#"
+                SELECT
+                    books.book_id,
+                    books.title,
+                    authors.name as author,
+                    books.isbn,
+                    books.description,
+                    COALESCE(
+                        json_agg(
+                            json_build_object('category_id', categories.category_id, 'name', categories.name)
+                        ) FILTER (WHERE categories.category_id IS NOT NULL),
+                        '[]'
+                    ) as "categories!: Json<Vec<CategoryRow>>"
+                FROM books
+                INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                INNER JOIN authors ON authors.author_id = book_authors.author_id
+                LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                WHERE authors.author_id = $1
+                GROUP BY books.book_id, authors.name
+                ORDER BY books.created_at DESC
+            "#,
+            author_id as _
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(rows.into_iter().map(Book::from).collect())
+    }
+
+    async fn find_by_category_with(
+        conn: &mut PgConnection,
+        category_id: CategoryId,
+    ) -> AppResult<Vec<Book>> {
+        let rows: Vec<BookRow> = sqlx::query_as!(
+            BookRow,
+            r#"
+                SELECT
+                    books.book_id,
+                    books.title,
+                    authors.name as author,
+                    books.isbn,
+                    books.description,
+                    COALESCE(
+                        json_agg(
+                            json_build_object('category_id', categories.category_id, 'name', categories.name)
+                        ) FILTER (WHERE categories.category_id IS NOT NULL),
+                        '[]'
+                    ) as "categories!: Json<Vec<CategoryRow>>"
+                FROM books
+                INNER JOIN book_authors ON book_authors.book_id = books.book_id
+                INNER JOIN authors ON authors.author_id = book_authors.author_id
+                LEFT JOIN book_categories ON book_categories.book_id = books.book_id
+                LEFT JOIN categories ON categories.category_id = book_categories.category_id
+                WHERE EXISTS (
+                    SELECT 1 FROM book_categories bc
+                    WHERE bc.book_id = books.book_id AND bc.category_id = $1
+                )
+                GROUP BY books.book_id, authors.name
+                ORDER BY books.created_at DESC
+            "#,
+            category_id as _
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(rows.into_iter().map(Book::from).collect())
+    }
+
+    async fn add_category_with(
+        conn: &mut PgConnection,
+        book_id: BookId,
+        category_id: CategoryId,
+    ) -> AppResult<()> {
+        let exists = sqlx::query!(
+            r#"
+                SELECT 1 as "exists!"
+                FROM book_categories
+                WHERE book_id = $1 AND category_id = $2
+            "#,
+            book_id as _,
+            category_id as _
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .is_some();
+
+        if exists {
+            return Err(AppError::DuplicateEntry(format!(
+                "Book (book_id: {}) is already assigned to category (category_id: {})",
+                book_id.raw(),
+                category_id.raw()
+            )));
+        }
+
+        sqlx::query!(
+            r#"
+                INSERT INTO book_categories (book_id, category_id) VALUES ($1, $2)
+            "#,
+            book_id as _,
+            category_id as _
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_category_with(
+        conn: &mut PgConnection,
+        book_id: BookId,
+        category_id: CategoryId,
+    ) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM book_categories WHERE book_id = $1 AND category_id = $2
+            "#,
+            book_id as _,
+            category_id as _
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(format!(
+                "Book (book_id: {}) is not assigned to category (category_id: {})",
+                book_id.raw(),
+                category_id.raw()
+            )));
+        }
+
+        Ok(())
+    }
+}
 
-        Ok(rows.map(Book::from))
+#[async_trait]
+impl BookRepository for BookRepositoryImpl {
+    async fn create(&self, event: CreateBook) -> AppResult<BookId> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::create_with(&mut conn, event).await
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<Book>> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::find_all_with(&mut conn).await
+    }
+
+    async fn find_all_paginated(
+        &self,
+        limit: i64,
+        cursor: Option<BookId>,
+        order: SortOrder,
+    ) -> AppResult<PaginatedBooks> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::find_all_paginated_with(&mut conn, limit, cursor, order).await
+    }
+
+    async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::find_by_id_with(&mut conn, book_id).await
+    }
+
+    async fn find_by_author(&self, author_id: AuthorId) -> AppResult<Vec<Book>> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::find_by_author_with(&mut conn, author_id).await
+    }
+
+    async fn find_by_category(&self, category_id: CategoryId) -> AppResult<Vec<Book>> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::find_by_category_with(&mut conn, category_id).await
+    }
+
+    async fn update(&self, event: UpdateBook) -> AppResult<()> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::update_with(&mut conn, event).await
+    }
+
+    async fn delete(&self, book_id: BookId) -> AppResult<()> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::delete_with(&mut conn, book_id).await
+    }
+
+    async fn add_category(&self, book_id: BookId, category_id: CategoryId) -> AppResult<()> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::add_category_with(&mut conn, book_id, category_id).await
+    }
+
+    async fn remove_category(&self, book_id: BookId, category_id: CategoryId) -> AppResult<()> {
+        let mut conn = self.db.acquire().await?;
+
+        Self::remove_category_with(&mut conn, book_id, category_id).await
     }
 }
 
@@ -83,41 +577,403 @@ impl BookRepository for BookRepositoryImpl {
 mod tests {
     use super::*;
 
+    /// トランザクションに束ねた `BookRepositoryImpl` 相当の操作一式。
+    /// `with_rollback` 経由でのみ生成する。
+    struct TxBookRepository<'a> {
+        conn: &'a mut sqlx::PgConnection,
+    }
+
+    impl<'a> TxBookRepository<'a> {
+        async fn create(&mut self, event: CreateBook) -> AppResult<BookId> {
+            BookRepositoryImpl::create_with(&mut *self.conn, event).await
+        }
+
+        async fn find_all(&mut self) -> AppResult<Vec<Book>> {
+            BookRepositoryImpl::find_all_with(&mut *self.conn).await
+        }
+
+        async fn find_by_id(&mut self, book_id: BookId) -> AppResult<Option<Book>> {
+            BookRepositoryImpl::find_by_id_with(&mut *self.conn, book_id).await
+        }
+
+        async fn update(&mut self, event: UpdateBook) -> AppResult<()> {
+            BookRepositoryImpl::update_with(&mut *self.conn, event).await
+        }
+
+        async fn delete(&mut self, book_id: BookId) -> AppResult<()> {
+            BookRepositoryImpl::delete_with(&mut *self.conn, book_id).await
+        }
+
+        async fn find_all_paginated(
+            &mut self,
+            limit: i64,
+            cursor: Option<BookId>,
+            order: SortOrder,
+        ) -> AppResult<PaginatedBooks> {
+            BookRepositoryImpl::find_all_paginated_with(&mut *self.conn, limit, cursor, order).await
+        }
+
+        async fn find_by_author(&mut self, author_id: AuthorId) -> AppResult<Vec<Book>> {
+            BookRepositoryImpl::find_by_author_with(&mut *self.conn, author_id).await
+        }
+
+        async fn find_by_category(&mut self, category_id: CategoryId) -> AppResult<Vec<Book>> {
+            BookRepositoryImpl::find_by_category_with(&mut *self.conn, category_id).await
+        }
+
+        async fn add_category(
+            &mut self,
+            book_id: BookId,
+            category_id: CategoryId,
+        ) -> AppResult<()> {
+            BookRepositoryImpl::add_category_with(&mut *self.conn, book_id, category_id).await
+        }
+
+        async fn remove_category(
+            &mut self,
+            book_id: BookId,
+            category_id: CategoryId,
+        ) -> AppResult<()> {
+            BookRepositoryImpl::remove_category_with(&mut *self.conn, book_id, category_id).await
+        }
+    }
+
+    /// プール上でトランザクションを開始し、そのトランザクションに束ねた
+    /// リポジトリをクロージャに渡す。クロージャの実行後は必ずロールバック
+    /// するため、`#[sqlx::test]` でプールを使い回しても各テストが
+    /// データベースをきれいな状態のまま終えられる。
+    async fn with_rollback<F, Fut, T>(pool: &sqlx::PgPool, f: F) -> anyhow::Result<T>
+    where
+        F: for<'a> FnOnce(TxBookRepository<'a>) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<T>>,
+    {
+        let mut tx = pool.begin().await?;
+        let repo = TxBookRepository { conn: &mut tx };
+        let result = f(repo).await;
+        tx.rollback().await?;
+        result
+    }
+
     #[sqlx::test]
-    async fn test_register_book(pool: sqlx::PgPool) -> anyhow::Result<()>{
-        // BookRepositoryImplを初期化
-        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool));
-
-        // 投入するための蔵書データを作成
-        let book = CreateBook {
-            title: "Test Title".into(),
-            author: "Test Author".into(),
-            isbn: "Test ISBN".into(),
-            description: "Test Description".into(),
-        };
+    async fn test_register_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        with_rollback(&pool, |mut repo| async move {
+            // 蔵書には著者の関連付けが必須なので、先に著者を1件作成しておく
+            let author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "Test Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let author_id = AuthorId::new(author_row.author_id);
 
-        // 蔵書データを投入すると正常終了することを確認
-        repo.create(book).await?;
+            // 投入するための蔵書データを作成
+            let book = CreateBook {
+                title: "Test Title".into(),
+                author_id,
+                isbn: "Test ISBN".into(),
+                description: "Test Description".into(),
+            };
 
-        // 蔵書の一覧を取得すると投入した1件だけ取得することを確認
-        let res = repo.find_all().await?;
-        assert_eq!(res.len(), 1);
+            // 蔵書データを投入すると、投入した蔵書のIDが返ってくることを確認
+            let book_id = repo.create(book).await?;
 
-        // 蔵書の一覧の最初のデータから蔵書IDを取得し、
-        // find_by_idメソッドでその蔵書データを取得できることを確認
-        let book_id = res[0].id;
-        let res = repo.find_by_id(book_id).await?;
-        assert!(res.is_some());
+            // 蔵書の一覧を取得すると投入した1件だけ取得することを確認
+            let res = repo.find_all().await?;
+            assert_eq!(res.len(), 1);
 
-        // 取得した蔵書データが CreateBook で投入した
-        // 蔵書データと一致することを確認
-        let Book { id, title, author, isbn, description } = res.unwrap();
-        assert_eq!(id, book_id);
-        assert_eq!(title, "Test Title");
-        assert_eq!(author, "Test Author");
-        assert_eq!(isbn, "Test ISBN");
-        assert_eq!(description, "Test Description");
+            // create() で返ってきた蔵書IDを使って
+            // find_by_idメソッドでその蔵書データを取得できることを確認
+            let res = repo.find_by_id(book_id).await?;
+            assert!(res.is_some());
 
-        Ok(())
+            // 取得した蔵書データが CreateBook で投入した
+            // 蔵書データと一致することを確認
+            let Book {
+                id,
+                title,
+                author,
+                isbn,
+                description,
+                categories,
+            } = res.unwrap();
+            assert_eq!(id, book_id);
+            assert_eq!(title, "Test Title");
+            assert_eq!(author, "Test Author");
+            assert_eq!(isbn, "Test ISBN");
+            assert_eq!(description, "Test Description");
+            assert!(categories.is_empty());
+
+            Ok(())
+        })
+        .await
     }
-}
\ No newline at end of file
+
+    #[sqlx::test]
+    async fn test_update_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        with_rollback(&pool, |mut repo| async move {
+            let author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "Original Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let original_author_id = AuthorId::new(author_row.author_id);
+
+            let new_author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "New Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let new_author_id = AuthorId::new(new_author_row.author_id);
+
+            let book_id = repo
+                .create(CreateBook {
+                    title: "Original Title".into(),
+                    author_id: original_author_id,
+                    isbn: "Original ISBN".into(),
+                    description: "Original Description".into(),
+                })
+                .await?;
+
+            // 著者を含めて更新すると、book_authors が新しい著者に
+            // 張り替えられていることを確認する
+            repo.update(UpdateBook {
+                book_id,
+                title: "New Title".into(),
+                author_id: new_author_id,
+                isbn: "New ISBN".into(),
+                description: "New Description".into(),
+            })
+            .await?;
+
+            let res = repo.find_by_id(book_id).await?.unwrap();
+            assert_eq!(res.title, "New Title");
+            assert_eq!(res.author, "New Author");
+            assert_eq!(res.isbn, "New ISBN");
+            assert_eq!(res.description, "New Description");
+
+            // 存在しない蔵書を更新しようとすると EntityNotFound を返すことを確認
+            let missing_result = repo
+                .update(UpdateBook {
+                    book_id: BookId::new(Uuid::new_v4()),
+                    title: "Doesn't matter".into(),
+                    author_id: new_author_id,
+                    isbn: "Doesn't matter".into(),
+                    description: "Doesn't matter".into(),
+                })
+                .await;
+            assert!(matches!(missing_result, Err(AppError::EntityNotFound(_))));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[sqlx::test]
+    async fn test_delete_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        with_rollback(&pool, |mut repo| async move {
+            let author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "Test Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let author_id = AuthorId::new(author_row.author_id);
+
+            let book_id = repo
+                .create(CreateBook {
+                    title: "Test Title".into(),
+                    author_id,
+                    isbn: "Test ISBN".into(),
+                    description: "Test Description".into(),
+                })
+                .await?;
+
+            repo.delete(book_id).await?;
+
+            let res = repo.find_by_id(book_id).await?;
+            assert!(res.is_none());
+
+            // 存在しない蔵書を削除しようとすると EntityNotFound を返すことを確認
+            let missing_result = repo.delete(book_id).await;
+            assert!(matches!(missing_result, Err(AppError::EntityNotFound(_))));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[sqlx::test]
+    async fn test_find_all_paginated(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        with_rollback(&pool, |mut repo| async move {
+            let author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "Test Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let author_id = AuthorId::new(author_row.author_id);
+
+            // with_rollback は1つの外側トランザクション内でテストを実行するため、
+            // now() はトランザクション開始時刻を返し続け、挿入順に関わらず
+            // 全行の created_at が同一マイクロ秒になってしまう。
+            // ソート順のテストが book_id の乱数タイブレークに落ちないよう、
+            // 行ごとに明示的な created_at を立てる。
+            let mut book_ids = Vec::new();
+            for i in 0..5 {
+                let book_id = repo
+                    .create(CreateBook {
+                        title: format!("Title {i}"),
+                        author_id,
+                        isbn: format!("ISBN {i}"),
+                        description: format!("Description {i}"),
+                    })
+                    .await?;
+                sqlx::query!(
+                    r#"UPDATE books SET created_at = now() + make_interval(secs => $1) WHERE book_id = $2"#,
+                    i as f64,
+                    book_id as _
+                )
+                .execute(&mut *repo.conn)
+                .await?;
+                book_ids.push(book_id);
+            }
+
+            // 降順の1ページ目: 最新2件が返り、next_cursor はページ内最後の
+            // （=2番目に新しい）本を指す
+            let page1 = repo.find_all_paginated(2, None, SortOrder::Desc).await?;
+            assert_eq!(
+                page1.items.iter().map(|b| b.id).collect::<Vec<_>>(),
+                vec![book_ids[4], book_ids[3]]
+            );
+            assert_eq!(page1.next_cursor, Some(book_ids[3]));
+
+            // カーソルを渡すと続きの2件が返ることを確認
+            let page2 = repo
+                .find_all_paginated(2, page1.next_cursor, SortOrder::Desc)
+                .await?;
+            assert_eq!(
+                page2.items.iter().map(|b| b.id).collect::<Vec<_>>(),
+                vec![book_ids[2], book_ids[1]]
+            );
+            assert_eq!(page2.next_cursor, Some(book_ids[1]));
+
+            // 最後のページには next_cursor が付かないことを確認
+            let page3 = repo
+                .find_all_paginated(2, page2.next_cursor, SortOrder::Desc)
+                .await?;
+            assert_eq!(
+                page3.items.iter().map(|b| b.id).collect::<Vec<_>>(),
+                vec![book_ids[0]]
+            );
+            assert_eq!(page3.next_cursor, None);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[sqlx::test]
+    async fn test_find_by_author_and_category(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        with_rollback(&pool, |mut repo| async move {
+            let author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "Test Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let author_id = AuthorId::new(author_row.author_id);
+
+            let other_author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "Other Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let other_author_id = AuthorId::new(other_author_row.author_id);
+
+            let category_row = sqlx::query!(
+                r#"INSERT INTO categories (name) VALUES ($1) RETURNING category_id"#,
+                "Test Category"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let category_id = CategoryId::new(category_row.category_id);
+
+            let book_id = repo
+                .create(CreateBook {
+                    title: "Test Title".into(),
+                    author_id,
+                    isbn: "Test ISBN".into(),
+                    description: "Test Description".into(),
+                })
+                .await?;
+
+            // 著者で検索すると一致する蔵書が、他の著者では見つからないことを確認
+            let by_author = repo.find_by_author(author_id).await?;
+            assert_eq!(by_author.len(), 1);
+            assert_eq!(by_author[0].id, book_id);
+            assert!(repo.find_by_author(other_author_id).await?.is_empty());
+
+            // カテゴリを割り当てる前は find_by_category にヒットしないことを確認
+            assert!(repo.find_by_category(category_id).await?.is_empty());
+
+            repo.add_category(book_id, category_id).await?;
+
+            let by_category = repo.find_by_category(category_id).await?;
+            assert_eq!(by_category.len(), 1);
+            assert_eq!(by_category[0].id, book_id);
+
+            // 同じ組み合わせをもう一度割り当てようとすると DuplicateEntry を返すことを確認
+            let duplicate_result = repo.add_category(book_id, category_id).await;
+            assert!(matches!(duplicate_result, Err(AppError::DuplicateEntry(_))));
+
+            repo.remove_category(book_id, category_id).await?;
+            assert!(repo.find_by_category(category_id).await?.is_empty());
+
+            // 割り当てられていない組み合わせを解除しようとすると EntityNotFound を返すことを確認
+            let missing_result = repo.remove_category(book_id, category_id).await;
+            assert!(matches!(missing_result, Err(AppError::EntityNotFound(_))));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[sqlx::test]
+    async fn test_create_duplicate_isbn(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        with_rollback(&pool, |mut repo| async move {
+            let author_row = sqlx::query!(
+                r#"INSERT INTO authors (name) VALUES ($1) RETURNING author_id"#,
+                "Test Author"
+            )
+            .fetch_one(&mut *repo.conn)
+            .await?;
+            let author_id = AuthorId::new(author_row.author_id);
+
+            repo.create(CreateBook {
+                title: "Title A".into(),
+                author_id,
+                isbn: "Duplicate ISBN".into(),
+                description: "Description A".into(),
+            })
+            .await?;
+
+            // books.isbn の UNIQUE 制約違反 (23505) が DuplicateEntry に
+            // 変換されることを確認
+            let duplicate_result = repo
+                .create(CreateBook {
+                    title: "Title B".into(),
+                    author_id,
+                    isbn: "Duplicate ISBN".into(),
+                    description: "Description B".into(),
+                })
+                .await;
+            assert!(matches!(duplicate_result, Err(AppError::DuplicateEntry(_))));
+
+            Ok(())
+        })
+        .await
+    }
+}
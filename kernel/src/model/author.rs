@@ -0,0 +1,7 @@
+use crate::model::id::AuthorId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub id: AuthorId,
+    pub name: String,
+}
@@ -0,0 +1,23 @@
+pub mod event;
+
+use crate::model::category::Category;
+use crate::model::id::BookId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Book {
+    pub id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub description: String,
+    pub categories: Vec<Category>,
+}
+
+/// `find_all_paginated` が返す一頁分の蔵書一覧。
+/// `next_cursor` が `Some` の場合、その値を次回の `cursor` に渡すことで
+/// 続きのページを取得できる。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaginatedBooks {
+    pub items: Vec<Book>,
+    pub next_cursor: Option<BookId>,
+}
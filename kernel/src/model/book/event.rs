@@ -0,0 +1,18 @@
+use crate::model::id::{AuthorId, BookId};
+
+#[derive(Debug)]
+pub struct CreateBook {
+    pub title: String,
+    pub author_id: AuthorId,
+    pub isbn: String,
+    pub description: String,
+}
+
+#[derive(Debug)]
+pub struct UpdateBook {
+    pub book_id: BookId,
+    pub title: String,
+    pub author_id: AuthorId,
+    pub isbn: String,
+    pub description: String,
+}
@@ -0,0 +1,7 @@
+use crate::model::id::CategoryId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Category {
+    pub id: CategoryId,
+    pub name: String,
+}
@@ -0,0 +1,44 @@
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct BookId(Uuid);
+
+impl BookId {
+    pub fn new(id: Uuid) -> Self {
+        Self(id)
+    }
+
+    pub fn raw(&self) -> Uuid {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct AuthorId(Uuid);
+
+impl AuthorId {
+    pub fn new(id: Uuid) -> Self {
+        Self(id)
+    }
+
+    pub fn raw(&self) -> Uuid {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type, serde::Deserialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct CategoryId(Uuid);
+
+impl CategoryId {
+    pub fn new(id: Uuid) -> Self {
+        Self(id)
+    }
+
+    pub fn raw(&self) -> Uuid {
+        self.0
+    }
+}
@@ -0,0 +1,5 @@
+pub mod author;
+pub mod book;
+pub mod category;
+pub mod id;
+pub mod list;
@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::book::{
+    event::{CreateBook, UpdateBook},
+    Book, PaginatedBooks,
+};
+use crate::model::id::{AuthorId, BookId, CategoryId};
+use crate::model::list::SortOrder;
+
+#[async_trait]
+pub trait BookRepository: Send + Sync + 'static {
+    async fn create(&self, event: CreateBook) -> AppResult<BookId>;
+    async fn find_all(&self) -> AppResult<Vec<Book>>;
+    async fn find_all_paginated(
+        &self,
+        limit: i64,
+        cursor: Option<BookId>,
+        order: SortOrder,
+    ) -> AppResult<PaginatedBooks>;
+    async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>>;
+    async fn find_by_author(&self, author_id: AuthorId) -> AppResult<Vec<Book>>;
+    async fn find_by_category(&self, category_id: CategoryId) -> AppResult<Vec<Book>>;
+    async fn update(&self, event: UpdateBook) -> AppResult<()>;
+    async fn delete(&self, book_id: BookId) -> AppResult<()>;
+    async fn add_category(&self, book_id: BookId, category_id: CategoryId) -> AppResult<()>;
+    async fn remove_category(&self, book_id: BookId, category_id: CategoryId) -> AppResult<()>;
+}
@@ -0,0 +1,58 @@
+use sqlx::error::DatabaseError;
+use thiserror::Error;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("Entity not found: {0}")]
+    EntityNotFound(String),
+
+    #[error("Duplicate entry: {0}")]
+    DuplicateEntry(String),
+
+    #[error("Foreign key violation: {0}")]
+    ForeignKeyViolation(String),
+
+    #[error("Check violation: {0}")]
+    CheckViolation(String),
+
+    #[error("Error occurred while processing the operation: {0}")]
+    SpecificOperationError(#[source] sqlx::Error),
+
+    #[error("Transaction failed: {0}")]
+    TransactionError(#[source] sqlx::Error),
+
+    #[error("Unexpected error: {0}")]
+    UnexpectedError(#[source] anyhow::Error),
+}
+
+/// Postgres のエラーコードごとに `AppError` の具体的なバリアントへ変換する。
+/// `?` や `.map_err(AppError::from)` で sqlx::Error から素直に変換できるようにし、
+/// 呼び出し元がユニーク制約違反・外部キー違反・チェック制約違反を一律
+/// `SpecificOperationError` として握りつぶさずに済むようにする。
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => {
+                AppError::EntityNotFound("the query returned no rows".to_string())
+            }
+            sqlx::Error::Database(db_err) => match db_err.code().as_deref() {
+                Some("23505") => AppError::DuplicateEntry(constraint_message(db_err.as_ref())),
+                Some("23503") => {
+                    AppError::ForeignKeyViolation(constraint_message(db_err.as_ref()))
+                }
+                Some("23514") => AppError::CheckViolation(constraint_message(db_err.as_ref())),
+                _ => AppError::SpecificOperationError(e),
+            },
+            _ => AppError::SpecificOperationError(e),
+        }
+    }
+}
+
+fn constraint_message(db_err: &(dyn DatabaseError + 'static)) -> String {
+    match db_err.constraint() {
+        Some(constraint) => format!("{} (constraint: {})", db_err.message(), constraint),
+        None => db_err.message().to_string(),
+    }
+}